@@ -33,12 +33,84 @@ fn split_args(args: &[String]) -> (&[String], &[String]) {
     (&args, &[])
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Value {
     Bool(String),
     String(String),
 }
 
+impl Value {
+    fn as_str(&self) -> &str {
+        match self {
+            Value::Bool(s) | Value::String(s) => s,
+        }
+    }
+}
+
+// The shells we know how to emit a completion script for.
+#[derive(Debug, Clone, Copy)]
+enum Shell {
+    Bash,
+    Zsh,
+}
+
+// Describes how a `takes_value` argument's raw string should be interpreted
+// and validated, borrowed from clap's `value_parser`/`PossibleValue` design.
+#[derive(Debug)]
+enum ValueParser<'a> {
+    // `range` is an optional inclusive `min..=max` bound on the parsed integer.
+    Integer { range: Option<(i64, i64)> },
+    PossibleValues(&'a [&'a str]),
+}
+
+impl<'a> ValueParser<'a> {
+    fn integer() -> Self {
+        ValueParser::Integer { range: None }
+    }
+
+    fn integer_range(min: i64, max: i64) -> Self {
+        ValueParser::Integer {
+            range: Some((min, max)),
+        }
+    }
+
+    fn possible_values(values: &'a [&'a str]) -> Self {
+        ValueParser::PossibleValues(values)
+    }
+
+    fn validate(&self, name: &str, raw: &str) -> Result<(), CMDError> {
+        match self {
+            ValueParser::Integer { range } => {
+                let value: i64 = raw.parse().map_err(|_| CMDError::InvalidValue {
+                    name: name.to_string(),
+                    reason: format!("'{}' is not a valid integer", raw),
+                })?;
+
+                if let Some((min, max)) = range {
+                    if value < *min || value > *max {
+                        return Err(CMDError::InvalidValue {
+                            name: name.to_string(),
+                            reason: format!("{} is out of range {}..={}", value, min, max),
+                        });
+                    }
+                }
+
+                Ok(())
+            }
+            ValueParser::PossibleValues(values) => {
+                if !values.contains(&raw) {
+                    return Err(CMDError::InvalidValue {
+                        name: name.to_string(),
+                        reason: format!("expected one of {:?}, got '{}'", values, raw),
+                    });
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
 // If we try to specify an Argument as one would in any other language, the
 // compiler will advice us to "consider introducing a named lifetime parameter".
 //
@@ -51,6 +123,7 @@ struct Argument<'a> {
     takes_value: bool,
     default_value: Option<Value>,
     user_value: Option<Value>,
+    value_parser: Option<ValueParser<'a>>,
 }
 
 impl<'a> Argument<'a> {
@@ -62,6 +135,7 @@ impl<'a> Argument<'a> {
             takes_value: false,
             default_value: None,
             user_value: None,
+            value_parser: None,
         }
     }
 
@@ -79,17 +153,85 @@ impl<'a> Argument<'a> {
         self.help = Some(help);
         self
     }
+
+    fn default_value(mut self, value: &'a str) -> Self {
+        self.default_value = Some(Value::String(value.to_string()));
+        self
+    }
+
+    fn value_parser(mut self, value_parser: ValueParser<'a>) -> Self {
+        self.value_parser = Some(value_parser);
+        self
+    }
 }
 
 #[derive(Debug)]
 enum CMDError {
     UnexpectedArgument(String),
     DuplicateArgument(String),
+    // The argument expects a value (`takes_value` is set) but there was
+    // nothing left in the iterator to consume.
+    MissingValue(String),
+    // A `required` argument never got a `user_value`, neither from the
+    // command line nor from a `default_value`.
+    MissingRequiredArgument(String),
+    // The first non-flag token didn't name any of our registered
+    // subcommands.
+    UnknownSubcommand(String),
+    // The raw value failed the argument's `ValueParser`.
+    InvalidValue { name: String, reason: String },
+    // More than one member of a non-`multiple` ArgGroup was set.
+    ConflictingArguments(Vec<String>),
+    // A `required` ArgGroup had none of its members set.
+    MissingGroup(String),
+    // `--help`/`-h` was passed; this carries the rendered help text rather
+    // than a real failure, so callers should print it and exit cleanly.
+    Help(String),
+}
+
+// Ports clap's `ArgGroup`: a named set of arguments this level of `Arguments`
+// can enforce relationships between, e.g. "at most one of" or "at least one
+// of", independently of whether any individual member is itself `required`.
+#[derive(Debug)]
+struct ArgGroup<'a> {
+    name: &'a str,
+    args: Vec<&'a str>,
+    required: bool,
+    multiple: bool,
+}
+
+impl<'a> ArgGroup<'a> {
+    fn new(name: &'a str) -> Self {
+        ArgGroup {
+            name,
+            args: Vec::new(),
+            required: false,
+            multiple: false,
+        }
+    }
+
+    fn arg(mut self, name: &'a str) -> Self {
+        self.args.push(name);
+        self
+    }
+
+    fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    fn multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
 }
 
 #[derive(Debug, Default)]
 struct Arguments<'a> {
     args: BTreeMap<&'a str, Argument<'a>>,
+    subcommands: BTreeMap<&'a str, Arguments<'a>>,
+    matched_subcommand: Option<&'a str>,
+    groups: Vec<ArgGroup<'a>>,
 }
 
 impl<'a> Arguments<'a> {
@@ -105,13 +247,30 @@ impl<'a> Arguments<'a> {
         self
     }
 
+    // Registers a nested `Arguments`, the same way `git` has `commit`/`push`
+    // subcommands that each define their own flags.
+    fn subcommand(mut self, name: &'a str, subcommand: Arguments<'a>) -> Self {
+        self.subcommands.insert(name, subcommand);
+        self
+    }
+
+    // Registers an `ArgGroup` whose members are looked up in this same
+    // level's `args`; its constraints are checked once the rest of parsing
+    // has finished. A member name that isn't in `args` just counts as unset.
+    fn group(mut self, group: ArgGroup<'a>) -> Self {
+        self.groups.push(group);
+        self
+    }
+
     // When validating command line arguments, we want to get a descriptive error
-    // if something is wrong, but we don't really care about the Ok value.
+    // if something is wrong, but we don't really care about the Ok value, beyond
+    // the stripped-of-its-prefix argument name, which the caller needs in order
+    // to look the `Argument` back up and fill in its value.
     //
     // If we make this function not borrow the variable (move the arguments), then
     // we will run into an issue because this function will move the Arguments.
     // See rustc --explain E0507 and try removing the '&' from self.
-    fn validate_arg(&self, arg: &str) -> Result<(), CMDError> {
+    fn validate_arg<'b>(&self, arg: &'b str) -> Result<&'b str, CMDError> {
         if !arg.starts_with(ARG_PREFIX) && !arg.starts_with(SHORT_ARG_PREFIX) {
             return Err(CMDError::UnexpectedArgument(arg.to_string()));
         }
@@ -132,31 +291,296 @@ impl<'a> Arguments<'a> {
             return Err(CMDError::DuplicateArgument(arg_name.to_string()));
         }
 
+        Ok(arg_name)
+    }
+
+    // Walks `args`, consuming a value out of the iterator for every
+    // `takes_value` argument it recognizes, then backfills defaults and
+    // enforces `required` before handing control back to the caller.
+    //
+    // If this level has any registered subcommands, the first non-flag
+    // token encountered (however many flags precede it) is looked up there;
+    // on a match every remaining token is routed to that subcommand's own
+    // `parse` instead of being treated as one of ours, and our own
+    // default/required/group finalization below still runs afterwards. A
+    // non-flag token at a level with no subcommands just falls through to
+    // `validate_arg`'s `UnexpectedArgument`.
+    // `bin_name` is only used to render `USAGE:` lines should `--help`/`-h`
+    // show up, so nested subcommands get it extended with their own name.
+    fn parse(&mut self, bin_name: &str, args: &[String]) -> Result<(), CMDError> {
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if arg == "--help" || arg == "-h" {
+                return Err(CMDError::Help(self.render_help(bin_name)));
+            }
+
+            let is_flag = arg.starts_with(ARG_PREFIX) || arg.starts_with(SHORT_ARG_PREFIX);
+
+            if !is_flag && !self.subcommands.is_empty() {
+                let name = self
+                    .subcommands
+                    .keys()
+                    .find(|name| ***name == arg[..])
+                    .copied()
+                    .ok_or_else(|| CMDError::UnknownSubcommand(arg.to_string()))?;
+
+                let rest: Vec<String> = iter.cloned().collect();
+                let subcommand_bin_name = format!("{} {}", bin_name, name);
+                let subcommand = self.subcommands.get_mut(name).unwrap();
+                subcommand.parse(&subcommand_bin_name, &rest)?;
+                self.matched_subcommand = Some(name);
+                break;
+            }
+
+            let arg_name = self.validate_arg(arg)?;
+
+            // `validate_arg` already confirmed `arg_name` is a key of `self.args`.
+            let argument = self.args.get_mut(arg_name).unwrap();
+
+            argument.user_value = Some(if argument.takes_value {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| CMDError::MissingValue(arg_name.to_string()))?;
+
+                if let Some(parser) = argument.value_parser.as_ref() {
+                    parser.validate(argument.name, value)?;
+                }
+
+                Value::String(value.to_string())
+            } else {
+                Value::Bool("true".to_string())
+            });
+        }
+
+        for argument in self.args.values_mut() {
+            if argument.user_value.is_none() {
+                argument.user_value = argument.default_value.clone();
+            }
+        }
+
+        for argument in self.args.values() {
+            if argument.required && argument.user_value.is_none() {
+                return Err(CMDError::MissingRequiredArgument(argument.name.to_string()));
+            }
+        }
+
+        for group in &self.groups {
+            let set: Vec<String> = group
+                .args
+                .iter()
+                .filter(|name| {
+                    self.args
+                        .get(**name)
+                        .is_some_and(|argument| argument.user_value.is_some())
+                })
+                .map(|name| name.to_string())
+                .collect();
+
+            if !group.multiple && set.len() > 1 {
+                return Err(CMDError::ConflictingArguments(set));
+            }
+
+            if group.required && set.is_empty() {
+                return Err(CMDError::MissingGroup(group.name.to_string()));
+            }
+        }
+
         Ok(())
     }
+
+    // Lets callers read back what `parse` resolved for a given argument,
+    // whether it came from the command line or from a `default_value`.
+    fn get(&self, name: &str) -> Option<&Value> {
+        self.args.get(name)?.user_value.as_ref()
+    }
+
+    // Renders a `USAGE:`/`OPTIONS:` block straight from the declared
+    // `Argument`s, the way clap's help and usage modules do.
+    fn render_help(&self, bin_name: &str) -> String {
+        let mut usage = format!("USAGE:\n    {}", bin_name);
+
+        for argument in self.args.values() {
+            let flag = if argument.takes_value {
+                format!("--{} <{}>", argument.name, argument.name.to_uppercase())
+            } else {
+                format!("--{}", argument.name)
+            };
+
+            if argument.required {
+                usage.push_str(&format!(" {}", flag));
+            } else {
+                usage.push_str(&format!(" [{}]", flag));
+            }
+        }
+
+        if !self.subcommands.is_empty() {
+            usage.push_str(" [SUBCOMMAND]");
+        }
+
+        // Column-align the help text by computing the longest flag column
+        // across the BTreeMap up front.
+        let rows: Vec<(String, &Argument)> = self
+            .args
+            .values()
+            .map(|argument| {
+                let flag = if argument.takes_value {
+                    format!("--{} <VALUE>", argument.name)
+                } else {
+                    format!("--{}", argument.name)
+                };
+                (flag, argument)
+            })
+            .collect();
+        let width = rows.iter().map(|(flag, _)| flag.len()).max().unwrap_or(0);
+
+        let mut options = String::from("\nOPTIONS:\n");
+        for (flag, argument) in &rows {
+            let help = argument.help.unwrap_or("");
+            let default = argument
+                .default_value
+                .as_ref()
+                .map(|value| format!(" [default: {}]", value.as_str()))
+                .unwrap_or_default();
+            options.push_str(&format!(
+                "    {:width$}    {}{}\n",
+                flag,
+                help,
+                default,
+                width = width
+            ));
+        }
+
+        format!("{}\n{}", usage, options)
+    }
+
+    // Returns the subcommand `parse` routed to, if any, alongside its own
+    // already-populated `Arguments`.
+    fn matched_subcommand(&self) -> Option<(&str, &Arguments)> {
+        let name = self.matched_subcommand?;
+        self.subcommands.get(name).map(|sub| (name, sub))
+    }
+
+    // Renders a completion script for `shell`, generated straight from the
+    // declared `Argument`s - no separate completions to keep in sync by hand.
+    fn generate_completions(&self, shell: Shell, bin_name: &str) -> String {
+        match shell {
+            Shell::Bash => self.bash_completions(bin_name),
+            Shell::Zsh => self.zsh_completions(bin_name),
+        }
+    }
+
+    fn bash_completions(&self, bin_name: &str) -> String {
+        // Iterating the BTreeMap (rather than a HashMap) keeps this output
+        // deterministic across runs.
+        let flags: Vec<String> = self.args.keys().map(|name| format!("--{}", name)).collect();
+
+        let value_flags: Vec<String> = self
+            .args
+            .values()
+            .filter(|argument| argument.takes_value)
+            .map(|argument| format!("--{}", argument.name))
+            .collect();
+
+        // When the previous word is a flag that takes a value, don't offer
+        // flag names as completions for that value slot - fall back to
+        // completing file paths instead.
+        let prev_case = if value_flags.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "    case \"$prev\" in\n        {}) COMPREPLY=( $(compgen -f -- \"$cur\") ); return 0 ;;\n    esac\n\n",
+                value_flags.join("|")
+            )
+        };
+
+        format!(
+            "_{bin}() {{\n    local cur prev\n    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"\n\n{prev_case}    COMPREPLY=( $(compgen -W \"{flags}\" -- \"$cur\") )\n}}\ncomplete -F _{bin} {bin}\n",
+            bin = bin_name,
+            prev_case = prev_case,
+            flags = flags.join(" "),
+        )
+    }
+
+    fn zsh_completions(&self, bin_name: &str) -> String {
+        let specs: Vec<String> = self
+            .args
+            .values()
+            .map(|argument| {
+                let help = argument.help.unwrap_or("");
+                if argument.takes_value {
+                    format!("    '--{}[{}]:{}:' ", argument.name, help, argument.name)
+                } else {
+                    format!("    '--{}[{}]' ", argument.name, help)
+                }
+            })
+            .collect();
+
+        format!(
+            "#compdef {bin}\n\n_arguments \\\n{specs}\n",
+            bin = bin_name,
+            specs = specs.join("\\\n"),
+        )
+    }
 }
 
+const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error"];
+
 // About `'static`
 // https://doc.rust-lang.org/stable/rust-by-example/scope/lifetime/static_lifetime.html
 fn build_cmd_arguments() -> Arguments<'static> {
     Arguments::new()
-        .insert_arg(
-            Argument::new("id")
-                .required(true)
-                .takes_value(true)
-                .help("jail ID"),
-        )
         .insert_arg(Argument::new("daemonize").help("Daemonize the jailer before execing"))
-}
-
-fn parse(app_arguments: &Arguments<'static>, args: &[String]) -> Result<(), CMDError> {
-    let mut iter = args.iter();
-
-    while let Some(arg) = iter.next() {
-        app_arguments.validate_arg(arg)?;
-    }
-
-    Ok(())
+        .subcommand(
+            "run",
+            Arguments::new()
+                .insert_arg(
+                    Argument::new("id")
+                        .required(true)
+                        .takes_value(true)
+                        .help("jail ID to run"),
+                )
+                .insert_arg(
+                    Argument::new("log-level")
+                        .takes_value(true)
+                        .default_value("info")
+                        .value_parser(ValueParser::possible_values(LOG_LEVELS))
+                        .help("Logging verbosity"),
+                )
+                .insert_arg(Argument::new("daemonize").help("Daemonize the jail before running it"))
+                .insert_arg(
+                    Argument::new("foreground").help("Keep the jail in the foreground"),
+                )
+                .group(
+                    ArgGroup::new("mode")
+                        .arg("daemonize")
+                        .arg("foreground")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            "exec",
+            Arguments::new()
+                .insert_arg(
+                    Argument::new("id")
+                        .required(true)
+                        .takes_value(true)
+                        .help("jail ID to exec into"),
+                )
+                .insert_arg(
+                    Argument::new("command")
+                        .required(true)
+                        .takes_value(true)
+                        .help("command to execute inside the jail"),
+                )
+                .insert_arg(
+                    Argument::new("timeout")
+                        .takes_value(true)
+                        .default_value("30")
+                        .value_parser(ValueParser::integer_range(1, 3600))
+                        .help("seconds to wait before killing the command"),
+                ),
+        )
 }
 
 fn main() {
@@ -165,8 +589,7 @@ fn main() {
     // We can use the collect() method of the iterator to transform the
     // iterator into a collection.
     // https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.collect
-    let args: Vec<String> = env::args().collect();
-    println!("process arguments: {:?}", args);
+    let raw_args: Vec<String> = env::args().collect();
 
     // For the sake of experimenting, let's say that we want to call out to
     // "somthing" else. Let's use the linux mentality and use `--` to signal the
@@ -175,43 +598,225 @@ fn main() {
     // use iterators).
     // We will also skip the very first value since that is the name of this
     // program.
-    let (args, extra_args) = split_args(&args[1..]);
-    println!("args: {:?}\nextra args: {:?}", args, extra_args);
-
-    // split_args shows how to search and find where something is inside of a
-    // collection. But, let's say you just want to know if something is inside
-    // of the collection.
-    // In this case, slices (like many other collections) have a `contains`
-    // method.
-    // https://doc.rust-lang.org/std/primitive.slice.html#method.contains
-    //
-    // Also note that the contains method expects as argument something of the
-    // same type as what it is in the slice. Recall that we have slices of
-    // Strings, so we must cast the string literal "--help" into a String type.
-    if args.contains(&"--help".to_string()) {
-        println!("we need help!");
+    let (args, extra_args) = split_args(&raw_args[1..]);
+
+    // These two generate a script meant to be `source`d or installed
+    // straight from our stdout. They are handled here, before `parse`,
+    // which doesn't know these flags and would reject them.
+    if args.iter().any(|arg| arg == "--generate-bash-completions") {
+        print!(
+            "{}",
+            build_cmd_arguments().generate_completions(Shell::Bash, "jailer")
+        );
+        process::exit(0);
     }
 
-    // If we wanted to avoid casting our string literal into a String we could
-    // also done the previous step as follows
-    if args.iter().any(|arg| arg == "--help") {
-        println!("yup, we need help");
+    if args.iter().any(|arg| arg == "--generate-zsh-completions") {
+        print!(
+            "{}",
+            build_cmd_arguments().generate_completions(Shell::Zsh, "jailer")
+        );
         process::exit(0);
     }
 
+    // Debug commentary only - goes to stderr so `--help`/`-h` (and any other
+    // real stdout output below) stays clean enough to pipe or redirect.
+    eprintln!("process arguments: {:?}", raw_args);
+    eprintln!("args: {:?}\nextra args: {:?}", args, extra_args);
+
     let argument = Argument::new("my-arg");
-    println!("my argument is: {:?}", argument);
+    eprintln!("my argument is: {:?}", argument);
 
-    let app_arguments = build_cmd_arguments();
-    println!("our parsed arguments are: {:?}", app_arguments);
+    let mut app_arguments = build_cmd_arguments();
 
-    match parse(&app_arguments, args) {
+    match app_arguments.parse("jailer", args) {
+        Err(CMDError::Help(text)) => {
+            print!("{}", text);
+            process::exit(0);
+        }
         Err(err) => {
             println!("oops: {:?}", err);
             process::exit(1);
         }
         _ => {
-            println!("we parsed your arguments!");
+            println!("we parsed your arguments: {:?}", app_arguments);
+            match app_arguments.matched_subcommand() {
+                Some((name, subcommand)) => {
+                    println!("subcommand: {} (id = {:?})", name, subcommand.get("id"));
+                }
+                None => println!("no subcommand given"),
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_fills_in_default_when_not_provided() {
+        let mut cmd = Arguments::new().insert_arg(
+            Argument::new("name")
+                .takes_value(true)
+                .default_value("world"),
+        );
+
+        cmd.parse("app", &args(&[])).unwrap();
+
+        assert!(matches!(cmd.get("name"), Some(Value::String(s)) if s == "world"));
+    }
+
+    #[test]
+    fn parse_prefers_user_value_over_default() {
+        let mut cmd = Arguments::new().insert_arg(
+            Argument::new("name")
+                .takes_value(true)
+                .default_value("world"),
+        );
+
+        cmd.parse("app", &args(&["--name", "alice"])).unwrap();
+
+        assert!(matches!(cmd.get("name"), Some(Value::String(s)) if s == "alice"));
+    }
+
+    #[test]
+    fn parse_errors_on_missing_value_at_end_of_args() {
+        let mut cmd = Arguments::new().insert_arg(Argument::new("name").takes_value(true));
+
+        let err = cmd.parse("app", &args(&["--name"])).unwrap_err();
+
+        assert!(matches!(err, CMDError::MissingValue(name) if name == "name"));
+    }
+
+    #[test]
+    fn parse_errors_on_missing_required_argument() {
+        let mut cmd =
+            Arguments::new().insert_arg(Argument::new("id").required(true).takes_value(true));
+
+        let err = cmd.parse("app", &args(&[])).unwrap_err();
+
+        assert!(matches!(err, CMDError::MissingRequiredArgument(name) if name == "id"));
+    }
+
+    #[test]
+    fn parse_errors_on_unknown_subcommand() {
+        let mut cmd = Arguments::new().subcommand("run", Arguments::new());
+
+        let err = cmd.parse("app", &args(&["walk"])).unwrap_err();
+
+        assert!(matches!(err, CMDError::UnknownSubcommand(name) if name == "walk"));
+    }
+
+    #[test]
+    fn parse_routes_to_matched_subcommand() {
+        let mut cmd = Arguments::new().subcommand(
+            "run",
+            Arguments::new().insert_arg(Argument::new("id").required(true).takes_value(true)),
+        );
+
+        cmd.parse("app", &args(&["run", "--id", "abc"])).unwrap();
+
+        let (name, subcommand) = cmd.matched_subcommand().unwrap();
+        assert_eq!(name, "run");
+        assert!(matches!(subcommand.get("id"), Some(Value::String(s)) if s == "abc"));
+    }
+
+    #[test]
+    fn value_parser_rejects_integer_out_of_range() {
+        let mut cmd = Arguments::new().insert_arg(
+            Argument::new("timeout")
+                .takes_value(true)
+                .value_parser(ValueParser::integer_range(1, 10)),
+        );
+
+        let err = cmd.parse("app", &args(&["--timeout", "20"])).unwrap_err();
+
+        assert!(matches!(err, CMDError::InvalidValue { name, .. } if name == "timeout"));
+    }
+
+    #[test]
+    fn value_parser_accepts_integer_in_range() {
+        let mut cmd = Arguments::new().insert_arg(
+            Argument::new("timeout")
+                .takes_value(true)
+                .value_parser(ValueParser::integer_range(1, 10)),
+        );
+
+        cmd.parse("app", &args(&["--timeout", "5"])).unwrap();
+
+        assert!(matches!(cmd.get("timeout"), Some(Value::String(s)) if s == "5"));
+    }
+
+    #[test]
+    fn value_parser_rejects_value_outside_possible_values() {
+        let mut cmd = Arguments::new().insert_arg(
+            Argument::new("log-level")
+                .takes_value(true)
+                .value_parser(ValueParser::possible_values(&["info", "debug"])),
+        );
+
+        let err = cmd
+            .parse("app", &args(&["--log-level", "verbose"]))
+            .unwrap_err();
+
+        assert!(matches!(err, CMDError::InvalidValue { name, .. } if name == "log-level"));
+    }
+
+    #[test]
+    fn arg_group_errors_on_conflicting_members() {
+        let mut cmd = Arguments::new()
+            .insert_arg(Argument::new("daemonize"))
+            .insert_arg(Argument::new("foreground"))
+            .group(
+                ArgGroup::new("mode")
+                    .arg("daemonize")
+                    .arg("foreground")
+                    .multiple(false),
+            );
+
+        let err = cmd
+            .parse("app", &args(&["--daemonize", "--foreground"]))
+            .unwrap_err();
+
+        assert!(matches!(err, CMDError::ConflictingArguments(members) if members.len() == 2));
+    }
+
+    #[test]
+    fn arg_group_errors_when_required_group_is_empty() {
+        let mut cmd = Arguments::new()
+            .insert_arg(Argument::new("daemonize"))
+            .insert_arg(Argument::new("foreground"))
+            .group(
+                ArgGroup::new("mode")
+                    .arg("daemonize")
+                    .arg("foreground")
+                    .required(true)
+                    .multiple(false),
+            );
+
+        let err = cmd.parse("app", &args(&[])).unwrap_err();
+
+        assert!(matches!(err, CMDError::MissingGroup(name) if name == "mode"));
+    }
+
+    #[test]
+    fn arg_group_passes_with_exactly_one_member_set() {
+        let mut cmd = Arguments::new()
+            .insert_arg(Argument::new("daemonize"))
+            .insert_arg(Argument::new("foreground"))
+            .group(
+                ArgGroup::new("mode")
+                    .arg("daemonize")
+                    .arg("foreground")
+                    .required(true)
+                    .multiple(false),
+            );
+
+        cmd.parse("app", &args(&["--daemonize"])).unwrap();
+    }
+}